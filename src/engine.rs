@@ -1,10 +1,20 @@
-use cozy_chess::{self, util, Board, Color, GameStatus, Move, Piece, PieceMoves};
+use cozy_chess::{self, util, Board, Color, GameStatus, Move, Piece, PieceMoves, Square};
 use rand::{seq::SliceRandom, thread_rng};
 use std::{
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    thread,
     time::{Duration, Instant},
 };
 
+const INF: i32 = 1_000_000;
+// large enough that no real evaluation score comes within MAX_PLY of it, so a
+// score that does must encode a forced mate instead
+const MATE: i32 = 30_000;
+const MAX_PLY: i32 = 128;
+
 pub struct GoInfo {
     pub wtime: Option<u32>,
     pub btime: Option<u32>,
@@ -16,6 +26,7 @@ pub struct GoInfo {
     pub mate: Option<u32>,
     pub movetime: Option<u32>,
     pub infinite: bool,
+    pub ponder: bool,
 }
 macro_rules! find_arg {
     ($split : ident , $x: expr, $y : ty) => {
@@ -48,6 +59,7 @@ impl GoInfo {
                     false
                 }
             },
+            ponder: split.contains(&"ponder"),
         };
         out
     }
@@ -60,6 +72,82 @@ pub struct Engine {
     board: Board,
     my_side: Color,
     search_stack: Vec<SearchStack>,
+    tt: TranspositionTable,
+    // a Lazy-SMP helper's own SearchInfo::nodes only counts its own work, so
+    // `go nodes N` needs this shared total instead to stop the whole search
+    // at N nodes rather than N per thread
+    nodes_searched: Arc<AtomicU64>,
+    threads: usize,
+    max_depth: u8,
+}
+
+pub const DEFAULT_TT_SIZE_MB: usize = 16;
+pub const DEFAULT_MAX_DEPTH: u8 = 64;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum NodeType {
+    Exact,
+    LowerBound,
+    UpperBound,
+}
+
+#[derive(Clone, Copy)]
+struct TTEntry {
+    hash: u64,
+    depth: u8,
+    score: i32,
+    node_type: NodeType,
+    best_move: Option<Move>,
+}
+
+// one lock per bucket rather than one lock over the whole table, so helper
+// threads in the Lazy-SMP search can probe/store concurrently without
+// serializing on a single table-wide mutex
+#[derive(Clone)]
+struct TranspositionTable {
+    entries: Arc<Vec<Mutex<Option<TTEntry>>>>,
+}
+
+impl TranspositionTable {
+    fn new(size_mb: usize) -> Self {
+        let count = (size_mb * 1024 * 1024) / std::mem::size_of::<Mutex<Option<TTEntry>>>();
+        let entries = (0..count.max(1)).map(|_| Mutex::new(None)).collect();
+        Self {
+            entries: Arc::new(entries),
+        }
+    }
+
+    fn clear(&self) {
+        for slot in self.entries.iter() {
+            *slot.lock().unwrap() = None;
+        }
+    }
+
+    fn index(&self, hash: u64) -> usize {
+        (hash % self.entries.len() as u64) as usize
+    }
+
+    fn probe(&self, hash: u64) -> Option<TTEntry> {
+        (*self.entries[self.index(hash)].lock().unwrap()).filter(|entry| entry.hash == hash)
+    }
+
+    fn store(
+        &self,
+        hash: u64,
+        depth: u8,
+        score: i32,
+        node_type: NodeType,
+        best_move: Option<Move>,
+    ) {
+        let index = self.index(hash);
+        *self.entries[index].lock().unwrap() = Some(TTEntry {
+            hash,
+            depth,
+            score,
+            node_type,
+            best_move,
+        });
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -96,7 +184,10 @@ impl SearchInfo {
 }
 pub struct Shared {
     pub stop: bool,
+    pub ponder_hit: bool,
 }
+
+#[derive(Clone, Copy)]
 pub enum EndCondition {
     Time(Instant),
     Nodes(u64),
@@ -122,12 +213,38 @@ impl Engine {
             board: Board::startpos(),
             my_side: Color::White,
             search_stack: vec![],
+            tt: TranspositionTable::new(DEFAULT_TT_SIZE_MB),
+            nodes_searched: Arc::new(AtomicU64::new(0)),
+            threads: 1,
+            max_depth: DEFAULT_MAX_DEPTH,
         }
     }
 
     pub fn setup_newgame(&mut self) {
         self.board = Board::startpos();
         self.search_info.reset();
+        self.tt.clear();
+    }
+
+    pub fn set_option(&mut self, name: &str, value: &str) {
+        match name {
+            "Hash" => {
+                if let Ok(size_mb) = value.parse::<usize>() {
+                    self.tt = TranspositionTable::new(size_mb.max(1));
+                }
+            }
+            "Threads" => {
+                if let Ok(threads) = value.parse::<usize>() {
+                    self.threads = threads.max(1);
+                }
+            }
+            "Depth" => {
+                if let Ok(depth) = value.parse::<u8>() {
+                    self.max_depth = depth.max(1);
+                }
+            }
+            _ => {}
+        }
     }
 
     pub fn set_position(&mut self, input: String) {
@@ -156,8 +273,9 @@ impl Engine {
 
         self.my_side = self.board.side_to_move();
     }
-    pub fn find_best_move(&mut self, info: &GoInfo) -> Move {
+    pub fn find_best_move(&mut self, info: &GoInfo) -> Option<Move> {
         self.search_info.reset();
+        self.nodes_searched.store(0, Ordering::Relaxed);
         // find run mode amongst : {infinite, time, depth, nodes, movetime}
         let end_cond;
         let t1 = Instant::now();
@@ -196,27 +314,190 @@ impl Engine {
         } else {
             panic!("No end condition findable!");
         }
-        self.search_stack = vec![Default::default(); 16];
-        let (out, score) = self.negamax(&self.board.clone(), &end_cond, 3);
-
-        let time = t1.elapsed().as_millis();
-        let nps = self.search_info.nodes * 1000 / (time as u64 + 1);
-        println!(
-            "info nodes {} time {} score {} nps {}",
-            self.search_info.nodes, time, score, nps,
-        );
+        self.search_stack = vec![Default::default(); self.max_depth as usize + 1];
+        // make sure a leftover `stop` from a previous search doesn't abort this one
+        self.shared.lock().unwrap().stop = false;
+
+        if info.ponder {
+            return self.ponder_search(&end_cond, t1);
+        }
+
+        let root_board = self.board.clone();
+        let threads = self.threads;
+        let mate_limit = info.mate;
+
+        let best_move = thread::scope(|scope| {
+            // Lazy SMP: helper threads race the same iterative-deepening search on
+            // a clone of the root position, sharing the transposition table so they
+            // warm it up and improve the main thread's move ordering
+            let helpers: Vec<_> = (1..threads)
+                .map(|_| {
+                    let mut helper = self.clone();
+                    let board = root_board.clone();
+                    let end_cond = &end_cond;
+                    scope.spawn(move || {
+                        helper.iterative_deepen(&board, end_cond, t1, false, mate_limit)
+                    })
+                })
+                .collect();
+
+            let (best_move, _) =
+                self.iterative_deepen(&root_board, &end_cond, t1, true, mate_limit);
+            // the main thread finished its search; tell the helpers to give up too
+            self.shared.lock().unwrap().stop = true;
+
+            let mut total_nodes = self.search_info.nodes;
+            for helper in helpers {
+                if let Ok((_, nodes)) = helper.join() {
+                    total_nodes += nodes;
+                }
+            }
 
-        return out.unwrap();
+            let time = t1.elapsed().as_millis();
+            let nps = total_nodes * 1000 / (time as u64 + 1);
+            println!("info nodes {} nps {} time {}", total_nodes, nps, time);
+
+            best_move
+        });
+
+        Some(best_move.unwrap())
+    }
+
+    // `go ponder` searches the position infinitely, assuming the opponent
+    // plays the predicted move, and never reports a `bestmove` on its own.
+    // `ponderhit` converts it into the timed search `real_end_cond` describes;
+    // a plain `stop` abandons it with nothing to report.
+    fn ponder_search(&mut self, real_end_cond: &EndCondition, t1: Instant) -> Option<Move> {
+        self.shared.lock().unwrap().ponder_hit = false;
+        let root_board = self.board.clone();
+
+        let mut active_end_cond = EndCondition::Infinite;
+        let mut ponder_hit_seen = false;
+        let mut best_move = None;
+        let mut best_score = 0;
+        let mut depth = 1;
+        loop {
+            let (out, score) =
+                self.negamax(&root_board.clone(), &active_end_cond, -INF, INF, depth, 0);
+
+            if self.shared.lock().unwrap().stop {
+                // before ponderhit, stop abandons the guess and reports nothing;
+                // after ponderhit, active_end_cond is the real timed search, so
+                // stop just ends it early like any other search
+                if ponder_hit_seen {
+                    break;
+                }
+                return None;
+            }
+            if let Some(mv) = out {
+                best_move = Some(mv);
+                best_score = score;
+            }
+
+            let mate_in = Self::mate_distance(best_score);
+            let time = t1.elapsed().as_millis();
+            let nps = self.search_info.nodes * 1000 / (time as u64 + 1);
+            let score = match mate_in {
+                Some(mate_in) => format!("mate {}", mate_in),
+                None => format!("cp {}", best_score),
+            };
+            println!(
+                "info depth {} score {} nodes {} nps {} time {} pv {}",
+                depth,
+                score,
+                self.search_info.nodes,
+                nps,
+                time,
+                best_move.map_or(String::new(), |mv| mv.to_string()),
+            );
+
+            if self.shared.lock().unwrap().ponder_hit {
+                active_end_cond = *real_end_cond;
+                ponder_hit_seen = true;
+                self.shared.lock().unwrap().ponder_hit = false;
+            }
+
+            if active_end_cond.met(self.nodes_searched.load(Ordering::Relaxed), depth)
+                || depth >= self.max_depth
+            {
+                break;
+            }
+            depth += 1;
+        }
+
+        best_move
+    }
+
+    fn iterative_deepen(
+        &mut self,
+        board: &Board,
+        end_cond: &EndCondition,
+        start: Instant,
+        report: bool,
+        mate_limit: Option<u32>,
+    ) -> (Option<Move>, u64) {
+        let mut best_move = None;
+        let mut best_score = 0;
+        let mut depth = 1;
+        loop {
+            let (out, score) = self.negamax(&board.clone(), end_cond, -INF, INF, depth, 0);
+
+            // a stop mid-iteration leaves the search half-done, so only the last
+            // fully-searched depth is allowed to update the root move/score
+            if self.shared.lock().unwrap().stop {
+                break;
+            }
+            if let Some(mv) = out {
+                best_move = Some(mv);
+                best_score = score;
+            }
+
+            let mate_in = Self::mate_distance(best_score);
+
+            if report {
+                let time = start.elapsed().as_millis();
+                let nps = self.search_info.nodes * 1000 / (time as u64 + 1);
+                let score = match mate_in {
+                    Some(mate_in) => format!("mate {}", mate_in),
+                    None => format!("cp {}", best_score),
+                };
+                println!(
+                    "info depth {} score {} nodes {} nps {} time {} pv {}",
+                    depth,
+                    score,
+                    self.search_info.nodes,
+                    nps,
+                    time,
+                    best_move.map_or(String::new(), |mv| mv.to_string()),
+                );
+            }
+
+            let found_mate_in_time = matches!((mate_in, mate_limit), (Some(n), Some(limit)) if n > 0 && n as u32 <= limit);
+
+            if end_cond.met(self.nodes_searched.load(Ordering::Relaxed), depth)
+                || depth >= self.max_depth
+                || found_mate_in_time
+            {
+                break;
+            }
+            depth += 1;
+        }
+
+        (best_move, self.search_info.nodes)
     }
 
     fn negamax(
         &mut self,
         board: &Board,
         end_condition: &EndCondition,
+        mut alpha: i32,
+        mut beta: i32,
         depth: u8,
+        ply: u8,
     ) -> (Option<Move>, i32) {
-        // end_condition.met(self.search_info.nodes, 0) ||
-        if self.shared.lock().unwrap().stop {
+        if self.shared.lock().unwrap().stop
+            || end_condition.met(self.nodes_searched.load(Ordering::Relaxed), 0)
+        {
             return (None, 0);
         }
         let cur_hash = board.hash();
@@ -224,16 +505,14 @@ impl Engine {
         if depth == 0 || board.status() != GameStatus::Ongoing {
             return match board.status() {
                 GameStatus::Drawn => (None, 0),
-                GameStatus::Won => {
-                    if board.side_to_move() == Color::White {
-                        (None, 1000)
-                    } else {
-                        (None, -1000)
-                    }
-                }
-                GameStatus::Ongoing => (None, Self::evaluate(&board)),
+                // the side to move has no legal moves and is in check: a loss,
+                // scored so that a mate found sooner (smaller ply) is worth more
+                GameStatus::Won => (None, -(MATE - ply as i32)),
+                GameStatus::Ongoing => (
+                    None,
+                    self.quiescence(board, end_condition, alpha, beta, ply),
+                ),
             };
-        // }
         } else if self.search_stack[(depth as usize + 1)..]
             .iter()
             .any(|item| item.board_hash == cur_hash)
@@ -241,7 +520,26 @@ impl Engine {
             return (None, 0); // detect repeated position in current search line, return draw if found
         }
 
-        let mut max_score = -1000;
+        let alpha_orig = alpha;
+        let tt_entry = self.tt.probe(cur_hash);
+        if let Some(entry) = tt_entry {
+            if entry.depth >= depth {
+                // the stored score is mate-distance-from-the-stored-node; re-root
+                // it onto this path's ply before using it
+                let entry_score = Self::from_tt_score(entry.score, ply);
+                self.search_info.tt_hits += 1;
+                match entry.node_type {
+                    NodeType::Exact => return (entry.best_move, entry_score),
+                    NodeType::LowerBound => alpha = alpha.max(entry_score),
+                    NodeType::UpperBound => beta = beta.min(entry_score),
+                }
+                if alpha >= beta {
+                    return (entry.best_move, entry_score);
+                }
+            }
+        }
+
+        let mut max_score = -INF;
         let mut best_move = None;
         let mut moves = vec![];
         board.generate_moves(|mves| {
@@ -250,36 +548,267 @@ impl Engine {
             }
             false
         });
+        // search the transposition table's best move first to improve ordering
+        if let Some(tt_move) = tt_entry.and_then(|entry| entry.best_move) {
+            if let Some(pos) = moves.iter().position(|&mv| mv == tt_move) {
+                moves.swap(0, pos);
+            }
+        }
         for mv in moves {
             self.search_info.nodes += 1;
+            self.nodes_searched.fetch_add(1, Ordering::Relaxed);
             let mut board = board.clone();
             board.play_unchecked(mv);
-            let (_, score) = self.negamax(&board, end_condition, depth - 1);
+            let (_, score) =
+                self.negamax(&board, end_condition, -beta, -alpha, depth - 1, ply + 1);
             let score = -score;
             if score > max_score {
                 max_score = score;
                 best_move = Some(mv);
-                // alpha = alpha.max(score);
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                self.search_info.cutoffs += 1;
+                break;
             }
         }
+
+        let node_type = if max_score <= alpha_orig {
+            NodeType::UpperBound
+        } else if max_score >= beta {
+            NodeType::LowerBound
+        } else {
+            NodeType::Exact
+        };
+        // strip this path's ply out of the mate distance before storing, so the
+        // entry is valid when reused at a different ply via transposition
+        let tt_score = Self::to_tt_score(max_score, ply);
+        self.tt.store(cur_hash, depth, tt_score, node_type, best_move);
+
         (best_move, max_score)
     }
 
+    // mate scores are ply-from-root-relative, which only means something on the
+    // path that produced them; rebase to/from a ply-independent "distance from
+    // this node" form so a transposed reuse at a different ply stays correct
+    fn to_tt_score(score: i32, ply: u8) -> i32 {
+        if score > MATE - MAX_PLY {
+            score + ply as i32
+        } else if score < -(MATE - MAX_PLY) {
+            score - ply as i32
+        } else {
+            score
+        }
+    }
+
+    fn from_tt_score(score: i32, ply: u8) -> i32 {
+        if score > MATE - MAX_PLY {
+            score - ply as i32
+        } else if score < -(MATE - MAX_PLY) {
+            score + ply as i32
+        } else {
+            score
+        }
+    }
+
+    // a depth-0 call straight into evaluate() walks into the horizon effect
+    // (e.g. stopping right before a free capture), so keep searching captures
+    // until the position is quiet
+    fn quiescence(
+        &mut self,
+        board: &Board,
+        end_condition: &EndCondition,
+        mut alpha: i32,
+        beta: i32,
+        ply: u8,
+    ) -> i32 {
+        if self.shared.lock().unwrap().stop
+            || end_condition.met(self.nodes_searched.load(Ordering::Relaxed), 0)
+        {
+            return alpha;
+        }
+        // a capture can walk straight into checkmate/stalemate; score that like
+        // negamax does instead of falling through to a material evaluation
+        match board.status() {
+            GameStatus::Drawn => return 0,
+            GameStatus::Won => return -(MATE - ply as i32),
+            GameStatus::Ongoing => {}
+        }
+        self.search_info.nodes += 1;
+        self.nodes_searched.fetch_add(1, Ordering::Relaxed);
+
+        let stand_pat = Self::evaluate(board);
+        if stand_pat >= beta {
+            return beta;
+        }
+        if stand_pat > alpha {
+            alpha = stand_pat;
+        }
+
+        let opponent = board.colors(!board.side_to_move());
+        let mut captures = vec![];
+        board.generate_moves(|mut mves| {
+            mves.to &= opponent;
+            for mv in mves {
+                captures.push(mv);
+            }
+            false
+        });
+
+        for mv in captures {
+            let mut child = board.clone();
+            child.play_unchecked(mv);
+            let score = -self.quiescence(&child, end_condition, -beta, -alpha, ply + 1);
+            if score >= beta {
+                return beta;
+            }
+            if score > alpha {
+                alpha = score;
+            }
+        }
+
+        alpha
+    }
+
+    // positive N means we deliver mate in N moves, negative means we get mated in N
+    fn mate_distance(score: i32) -> Option<i32> {
+        if score > MATE - MAX_PLY {
+            Some((MATE - score + 1) / 2)
+        } else if score < -(MATE - MAX_PLY) {
+            Some(-((MATE + score + 1) / 2))
+        } else {
+            None
+        }
+    }
+
+    fn piece_value(piece: Piece) -> i32 {
+        match piece {
+            Piece::Pawn => 100,
+            Piece::Knight => 320,
+            Piece::Bishop => 330,
+            Piece::Rook => 500,
+            Piece::Queen => 900,
+            Piece::King => 0,
+        }
+    }
+
+    // the tables are written rank 1 (White's back rank) first, rank 8 last;
+    // mirror the rank to read a Black piece's bonus from White's perspective
+    fn pst_index(square: Square, color: Color) -> usize {
+        let file = square.file() as usize;
+        let rank = match color {
+            Color::White => square.rank() as usize,
+            Color::Black => 7 - square.rank() as usize,
+        };
+        rank * 8 + file
+    }
+
+    fn piece_square_value(piece: Piece, square: Square, color: Color) -> i32 {
+        let table = match piece {
+            Piece::Pawn => &PAWN_PST,
+            Piece::Knight => &KNIGHT_PST,
+            Piece::Bishop => &BISHOP_PST,
+            Piece::Rook => &ROOK_PST,
+            Piece::Queen => &QUEEN_PST,
+            Piece::King => &KING_PST,
+        };
+        table[Self::pst_index(square, color)]
+    }
+
     fn evaluate(board: &Board) -> i32 {
-        let who_movin = if (board.side_to_move() == Color::White) {
+        let who_movin = if board.side_to_move() == Color::White {
             1
         } else {
             -1
         };
-        let white_material = board.colors(Color::White).len() as i32;
-        let black_material = board.colors(Color::Black).len() as i32;
-        if (white_material != black_material) {
-            // println!("GHEHE");
+
+        let mut score = 0;
+        for color in [Color::White, Color::Black] {
+            let sign = if color == Color::White { 1 } else { -1 };
+            for piece in Piece::ALL {
+                for square in board.colored_pieces(color, piece) {
+                    score +=
+                        sign * (Self::piece_value(piece) + Self::piece_square_value(piece, square, color));
+                }
+            }
         }
-        return (white_material - black_material) * who_movin;
+
+        score * who_movin
     }
 }
 
+#[rustfmt::skip]
+const PAWN_PST: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+     5, 10, 10,-20,-20, 10, 10,  5,
+     5, -5,-10,  0,  0,-10, -5,  5,
+     0,  0,  0, 20, 20,  0,  0,  0,
+     5,  5, 10, 25, 25, 10,  5,  5,
+    10, 10, 20, 30, 30, 20, 10, 10,
+    50, 50, 50, 50, 50, 50, 50, 50,
+     0,  0,  0,  0,  0,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const KNIGHT_PST: [i32; 64] = [
+    -50,-40,-30,-30,-30,-30,-40,-50,
+    -40,-20,  0,  5,  5,  0,-20,-40,
+    -30,  5, 10, 15, 15, 10,  5,-30,
+    -30,  0, 15, 20, 20, 15,  0,-30,
+    -30,  5, 15, 20, 20, 15,  5,-30,
+    -30,  0, 10, 15, 15, 10,  0,-30,
+    -40,-20,  0,  0,  0,  0,-20,-40,
+    -50,-40,-30,-30,-30,-30,-40,-50,
+];
+
+#[rustfmt::skip]
+const BISHOP_PST: [i32; 64] = [
+    -20,-10,-10,-10,-10,-10,-10,-20,
+    -10,  5,  0,  0,  0,  0,  5,-10,
+    -10, 10, 10, 10, 10, 10, 10,-10,
+    -10,  0, 10, 10, 10, 10,  0,-10,
+    -10,  5,  5, 10, 10,  5,  5,-10,
+    -10,  0,  5, 10, 10,  5,  0,-10,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -20,-10,-10,-10,-10,-10,-10,-20,
+];
+
+#[rustfmt::skip]
+const ROOK_PST: [i32; 64] = [
+     0,  0,  0,  5,  5,  0,  0,  0,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+     5, 10, 10, 10, 10, 10, 10,  5,
+     0,  0,  0,  0,  0,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const QUEEN_PST: [i32; 64] = [
+    -20,-10,-10, -5, -5,-10,-10,-20,
+    -10,  0,  5,  0,  0,  0,  0,-10,
+    -10,  5,  5,  5,  5,  5,  0,-10,
+      0,  0,  5,  5,  5,  5,  0, -5,
+     -5,  0,  5,  5,  5,  5,  0, -5,
+    -10,  0,  5,  5,  5,  5,  0,-10,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -20,-10,-10, -5, -5,-10,-10,-20,
+];
+
+#[rustfmt::skip]
+const KING_PST: [i32; 64] = [
+     20, 30, 10,  0,  0, 10, 30, 20,
+     20, 20,  0,  0,  0,  0, 20, 20,
+    -10,-20,-20,-20,-20,-20,-20,-10,
+    -20,-30,-30,-40,-40,-30,-30,-20,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+];
+
 #[allow(dead_code)]
 fn perft(board: &Board, depth: u8) -> u64 {
     if depth == 0 {
@@ -300,9 +829,104 @@ fn perft(board: &Board, depth: u8) -> u64 {
 
 #[cfg(test)]
 mod tests {
-    use cozy_chess::Board;
+    use std::sync::{Arc, Mutex};
+
+    use cozy_chess::{Board, Color, Piece, Square};
 
-    use super::perft;
+    use super::{
+        perft, EndCondition, Engine, GoInfo, NodeType, SearchStack, Shared, TranspositionTable,
+        INF, MATE, MAX_PLY,
+    };
+
+    fn new_engine() -> Engine {
+        Engine::new(Arc::new(Mutex::new(Shared {
+            stop: false,
+            ponder_hit: false,
+        })))
+    }
+
+    #[test]
+    fn negamax_finds_mate_in_one() {
+        // Rook on e1 delivers back-rank mate with Re8#
+        let board = Board::from_fen("6k1/5ppp/8/8/8/8/8/4R2K w - - 0 1", false).unwrap();
+        let mut engine = new_engine();
+        engine.search_stack = vec![SearchStack::default(); 4];
+
+        let (best_move, score) =
+            engine.negamax(&board, &EndCondition::Depth(1), -INF, INF, 1, 0);
+
+        assert_eq!(best_move.map(|mv| mv.to_string()), Some("e1e8".to_string()));
+        assert_eq!(Engine::mate_distance(score), Some(1));
+    }
+
+    #[test]
+    fn cloned_engine_shares_transposition_table_with_original() {
+        // Lazy-SMP helper threads run on a clone of the Engine; they only
+        // help if their clone's TT entries land in the same shared table
+        let engine = new_engine();
+        let helper = engine.clone();
+
+        engine.tt.store(7, 3, 99, NodeType::Exact, None);
+
+        let entry = helper
+            .tt
+            .probe(7)
+            .expect("a clone should see entries stored through the original");
+        assert_eq!(entry.score, 99);
+    }
+
+    #[test]
+    fn go_info_parses_the_ponder_flag() {
+        let info = GoInfo::new(String::from("ponder wtime 100 btime 100"));
+        assert!(info.ponder);
+
+        let info = GoInfo::new(String::from("wtime 100 btime 100"));
+        assert!(!info.ponder);
+    }
+
+    #[test]
+    fn transposition_table_round_trips_entries() {
+        let tt = TranspositionTable::new(1);
+        assert!(tt.probe(42).is_none());
+
+        tt.store(42, 6, 123, NodeType::Exact, None);
+        let entry = tt.probe(42).expect("entry should be present after store");
+        assert_eq!(entry.depth, 6);
+        assert_eq!(entry.score, 123);
+        assert_eq!(entry.node_type, NodeType::Exact);
+
+        // a probe for a different hash landing in the same bucket must not
+        // return this entry
+        assert!(tt.probe(42 + tt.entries.len() as u64).is_none());
+    }
+
+    #[test]
+    fn piece_square_tables_mirror_between_colors() {
+        // a White piece on its 2nd rank should score the same as the same
+        // piece for Black on its mirrored (7th rank) square
+        for piece in Piece::ALL {
+            let white_value = Engine::piece_square_value(piece, Square::E2, Color::White);
+            let black_value = Engine::piece_square_value(piece, Square::E7, Color::Black);
+            assert_eq!(white_value, black_value);
+        }
+    }
+
+    #[test]
+    fn mate_distance_detects_wins_and_losses() {
+        assert_eq!(Engine::mate_distance(0), None);
+        assert_eq!(Engine::mate_distance(100), None);
+        // just below the mate threshold: still an ordinary score
+        assert_eq!(Engine::mate_distance(MATE - MAX_PLY), None);
+        // just above it: the shortest and longest mates this side can report
+        assert_eq!(Engine::mate_distance(MATE - MAX_PLY + 1), Some(MAX_PLY / 2));
+        assert_eq!(Engine::mate_distance(MATE - 1), Some(1));
+        // losing mates mirror the winning ones
+        assert_eq!(Engine::mate_distance(-(MATE - 1)), Some(-1));
+        assert_eq!(
+            Engine::mate_distance(-(MATE - MAX_PLY + 1)),
+            Some(-(MAX_PLY / 2))
+        );
+    }
 
     #[test]
     fn perft_all() {
@@ -27,26 +27,40 @@ fn main() {
     println!("id name tunaturk");
     println!("id author BrokenKeyboard");
 
+    println!("option name Hash type spin default 16 min 1 max 1024");
+    println!("option name Threads type spin default 1 min 1 max 64");
+    println!("option name Depth type spin default 64 min 1 max 64");
+
     println!("uciok");
 
     let (send, recv) = channel::<SearchMessage>();
-    let shared = Arc::new(Mutex::new(Shared { stop: false }));
+    let shared = Arc::new(Mutex::new(Shared {
+        stop: false,
+        ponder_hit: false,
+    }));
     let shared_for_thread = Arc::clone(&shared);
     thread::spawn(move || {
         let mut search = Engine::new(Arc::clone(&shared_for_thread));
         while let Ok(message) = recv.recv() {
             match message {
                 SearchMessage::NewGame => {
-                    shared_for_thread.lock().expect("error").stop = false;
+                    let mut shared = shared_for_thread.lock().expect("error");
+                    shared.stop = false;
+                    shared.ponder_hit = false;
+                    drop(shared);
                     search.setup_newgame();
                 }
                 SearchMessage::Go(things) => {
-                    let best_move = search.find_best_move(&things);
-                    println!("bestmove {}", best_move);
+                    if let Some(best_move) = search.find_best_move(&things) {
+                        println!("bestmove {}", best_move);
+                    }
                 }
                 SearchMessage::SetPosition(info) => {
                     search.set_position(info);
                 }
+                SearchMessage::SetOption(name, value) => {
+                    search.set_option(&name, &value);
+                }
                 SearchMessage::Ready => {
                     println!("readyok");
                 }
@@ -80,7 +94,20 @@ fn main() {
             "isready" => {
                 send.send(SearchMessage::Ready).unwrap();
             }
-            "ponderhit" => todo!(),
+            "setoption" => {
+                let rest = input.get(10..).unwrap_or("");
+                if let Some(name_start) = rest.find("name ") {
+                    let after_name = &rest[name_start + 5..];
+                    if let Some(value_start) = after_name.find(" value ") {
+                        let name = after_name[..value_start].trim().to_string();
+                        let value = after_name[value_start + 7..].trim().to_string();
+                        send.send(SearchMessage::SetOption(name, value)).unwrap();
+                    }
+                }
+            }
+            "ponderhit" => {
+                shared.lock().unwrap().ponder_hit = true;
+            }
             "quit" => {
                 break;
             }
@@ -92,6 +119,7 @@ fn main() {
 enum SearchMessage {
     NewGame,
     SetPosition(String),
+    SetOption(String, String),
     Go(GoInfo),
     Ready,
 }